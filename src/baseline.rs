@@ -0,0 +1,139 @@
+use crate::{RegressionTest, RegressionTestStatus};
+use std::path::{Path, PathBuf};
+
+/// Maximum Hamming distance between perceptual hashes that still counts as a visual match.
+pub const DEFAULT_HAMMING_THRESHOLD: u32 = 10;
+
+/// Stores baseline `RegressionTest` snapshots as JSON files keyed by component + viewport.
+pub struct BaselineStore {
+    root: PathBuf,
+}
+
+impl BaselineStore {
+    pub fn new<P: AsRef<Path>>(root: P) -> Self {
+        Self {
+            root: root.as_ref().to_path_buf(),
+        }
+    }
+
+    fn path_for(&self, component: &str, viewport: &str) -> PathBuf {
+        self.root
+            .join(crate::artifact_file_name(component, viewport, "json"))
+    }
+
+    pub fn load(&self, component: &str, viewport: &str) -> Option<RegressionTest> {
+        let content = std::fs::read_to_string(self.path_for(component, viewport)).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    pub fn save(&self, test: &RegressionTest) -> std::io::Result<()> {
+        std::fs::create_dir_all(&self.root)?;
+        let content = serde_json::to_string_pretty(test).unwrap_or_else(|error| {
+            panic!("Failed to serialize baseline for {} {}", test.component, error);
+        });
+        std::fs::write(self.path_for(&test.component, &test.viewport), content)
+    }
+
+    fn screenshot_path_for(&self, component: &str, viewport: &str) -> PathBuf {
+        self.root
+            .join(crate::artifact_file_name(component, viewport, "png"))
+    }
+
+    /// Loads the baseline screenshot saved alongside the JSON record, if any.
+    pub fn load_screenshot(&self, component: &str, viewport: &str) -> Option<Vec<u8>> {
+        std::fs::read(self.screenshot_path_for(component, viewport)).ok()
+    }
+
+    pub fn save_screenshot(
+        &self,
+        component: &str,
+        viewport: &str,
+        bytes: &[u8],
+    ) -> std::io::Result<()> {
+        std::fs::create_dir_all(&self.root)?;
+        std::fs::write(self.screenshot_path_for(component, viewport), bytes)
+    }
+}
+
+/// Hamming distance between two hex-encoded perceptual hashes, i.e. the number of differing bits.
+fn hamming_distance(a: &str, b: &str) -> Option<u32> {
+    let a = u64::from_str_radix(a, 16).ok()?;
+    let b = u64::from_str_radix(b, 16).ok()?;
+    Some((a ^ b).count_ones())
+}
+
+/// Compares a freshly captured test against its baseline (if any) and decides the resulting status.
+///
+/// When no baseline exists, `compare_only` selects between `Skipped` (pure comparison runs) and
+/// `Created` (the baseline is about to be written for the first time).
+pub fn compare(
+    current: &RegressionTest,
+    baseline: Option<RegressionTest>,
+    threshold: u32,
+    compare_only: bool,
+) -> (RegressionTestStatus, RegressionTest) {
+    match baseline {
+        None => {
+            let status = if compare_only {
+                RegressionTestStatus::Skipped
+            } else {
+                RegressionTestStatus::Created
+            };
+            (status, current.clone())
+        }
+        Some(expected) => {
+            let dom_matches = current.dom_hash == expected.dom_hash;
+            let style_matches = current.style_hash == expected.style_hash;
+            let visual_distance =
+                hamming_distance(&current.visual_hash, &expected.visual_hash).unwrap_or(u32::MAX);
+
+            let status = if dom_matches && style_matches && visual_distance <= threshold {
+                RegressionTestStatus::Passed
+            } else {
+                RegressionTestStatus::Failed
+            };
+
+            (status, expected)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test(visual_hash: &str, dom_hash: &str, style_hash: &str) -> RegressionTest {
+        RegressionTest {
+            component: "Button".to_string(),
+            viewport: "1920x1080".to_string(),
+            dom_hash: dom_hash.to_string(),
+            style_hash: style_hash.to_string(),
+            visual_hash: visual_hash.to_string(),
+            timestamp: 0,
+        }
+    }
+
+    #[test]
+    fn passes_when_distance_equals_threshold() {
+        let baseline = test("0", "dom", "style");
+        let current = test("3ff", "dom", "style"); // 10 differing bits
+        let (status, _) = compare(&current, Some(baseline), 10, false);
+        assert!(matches!(status, RegressionTestStatus::Passed));
+    }
+
+    #[test]
+    fn fails_when_distance_exceeds_threshold() {
+        let baseline = test("0", "dom", "style");
+        let current = test("7ff", "dom", "style"); // 11 differing bits
+        let (status, _) = compare(&current, Some(baseline), 10, false);
+        assert!(matches!(status, RegressionTestStatus::Failed));
+    }
+
+    #[test]
+    fn dom_mismatch_fails_even_with_identical_visual_hash() {
+        let baseline = test("0", "dom-a", "style");
+        let current = test("0", "dom-b", "style");
+        let (status, _) = compare(&current, Some(baseline), 10, false);
+        assert!(matches!(status, RegressionTestStatus::Failed));
+    }
+}
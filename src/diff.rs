@@ -0,0 +1,50 @@
+use image::{DynamicImage, Rgba, RgbaImage};
+
+/// Sum-of-channel-deltas above which a pixel is considered changed.
+const CHANGE_THRESHOLD: u32 = 24;
+
+/// Builds a visual diff image: changed pixels are highlighted in magenta over a dimmed copy of
+/// `current`. When the two images differ in size, the result is padded to the larger bounds
+/// rather than erroring, treating out-of-bounds pixels as changed.
+pub fn diff_images(current: &DynamicImage, baseline: &DynamicImage) -> RgbaImage {
+    let width = current.width().max(baseline.width());
+    let height = current.height().max(baseline.height());
+
+    let current = current.to_rgba8();
+    let baseline = baseline.to_rgba8();
+
+    let mut out = RgbaImage::new(width, height);
+
+    for y in 0..height {
+        for x in 0..width {
+            let current_pixel = current.get_pixel_checked(x, y).copied();
+            let baseline_pixel = baseline.get_pixel_checked(x, y).copied();
+
+            let pixel = match (current_pixel, baseline_pixel) {
+                (Some(c), Some(b)) if pixel_delta(c, b) <= CHANGE_THRESHOLD => dim(c),
+                (Some(_), Some(_)) | (Some(_), None) | (None, Some(_)) => highlight(),
+                (None, None) => Rgba([0, 0, 0, 0]),
+            };
+
+            out.put_pixel(x, y, pixel);
+        }
+    }
+
+    out
+}
+
+fn pixel_delta(a: Rgba<u8>, b: Rgba<u8>) -> u32 {
+    let dr = (a[0] as i32 - b[0] as i32).unsigned_abs();
+    let dg = (a[1] as i32 - b[1] as i32).unsigned_abs();
+    let db = (a[2] as i32 - b[2] as i32).unsigned_abs();
+    let da = (a[3] as i32 - b[3] as i32).unsigned_abs();
+    dr + dg + db + da
+}
+
+fn dim(pixel: Rgba<u8>) -> Rgba<u8> {
+    Rgba([pixel[0] / 3, pixel[1] / 3, pixel[2] / 3, pixel[3]])
+}
+
+fn highlight() -> Rgba<u8> {
+    Rgba([255, 0, 255, 255])
+}
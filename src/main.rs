@@ -1,10 +1,21 @@
+mod baseline;
+mod diff;
+mod report;
+mod watch;
+
+use baseline::BaselineStore;
+use report::Reporter;
 use blockhash::blockhash64;
-use chromiumoxide::browser::{Browser, BrowserConfig, HeadlessMode};
+use chromiumoxide::browser::{Browser, BrowserConfig};
+use chromiumoxide::cdp::browser_protocol::emulation::SetDeviceMetricsOverrideParams;
 use chromiumoxide::cdp::browser_protocol::page::CaptureScreenshotFormat;
 use chromiumoxide::page::ScreenshotParams;
-use futures::StreamExt;
+use futures::stream::{self, StreamExt};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
 use regex::Regex;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use sha1::{Digest, Sha1};
 use std::path::{Path, PathBuf};
 use std::process::Stdio;
@@ -15,6 +26,27 @@ use walkdir::WalkDir;
 struct Config {
     base_url: String,
     start_command: String,
+    #[serde(default = "default_viewports")]
+    viewports: Vec<String>,
+}
+
+fn default_viewports() -> Vec<String> {
+    vec![String::from("1920x1080")]
+}
+
+/// Parses a `WIDTHxHEIGHT` viewport spec such as `"375x667"`.
+fn parse_viewport(spec: &str) -> Result<(u32, u32), Box<dyn std::error::Error>> {
+    let (width, height) = spec
+        .split_once('x')
+        .ok_or_else(|| format!("Invalid viewport '{}', expected WIDTHxHEIGHT", spec))?;
+    Ok((width.parse()?, height.parse()?))
+}
+
+/// Builds a filesystem-safe `<component>__<viewport>.<extension>` file name, shared by the
+/// baseline store and `--output-dir` so both use the same sanitization rule.
+fn artifact_file_name(component: &str, viewport: &str, extension: &str) -> String {
+    let safe_component = component.replace(['/', '\\'], "_");
+    format!("{}__{}.{}", safe_component, viewport, extension)
 }
 
 #[derive(Debug, Deserialize)]
@@ -22,6 +54,10 @@ struct Story {
     path: PathBuf,
     name: String,
     component_name: String,
+    /// Set when the story is annotated `// @visage-only`, restricting the run to the stories so tagged.
+    only: bool,
+    /// Set when the story is annotated `// @visage-ignore`, excluding it from the run.
+    ignore: bool,
 }
 
 impl std::fmt::Display for Story {
@@ -45,7 +81,7 @@ struct RegressionTestResult {
     expected_test: RegressionTest,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 struct RegressionTest {
     component: String,
     viewport: String,
@@ -55,13 +91,39 @@ struct RegressionTest {
     timestamp: u64,
 }
 
+/// Config file names looked up (in order) in the cwd and `~/.config`.
+const CONFIG_FILE_NAMES: [&str; 4] = ["visage.json", "visage.yaml", "visage.yml", "visage.toml"];
+
+fn find_config_path(cwd: &Path, home_dir: &Path) -> Option<PathBuf> {
+    for dir in [cwd, &home_dir.join(".config")] {
+        for name in CONFIG_FILE_NAMES {
+            let candidate = dir.join(name);
+            if file_exists(&candidate) {
+                return Some(candidate);
+            }
+        }
+    }
+    None
+}
+
 fn load_config<P: AsRef<Path>>(path: P) -> Result<Config, Box<dyn std::error::Error>> {
+    let path = path.as_ref();
     let config_content = std::fs::read_to_string(path).unwrap_or_else(|error| {
         panic!("Failed to read config file {}", error);
     });
-    let config: Config = serde_json::from_str(&config_content).unwrap_or_else(|error| {
-        panic!("Failed to parse config file {}", error);
-    });
+
+    let config: Config = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("yaml") | Some("yml") => serde_yaml::from_str(&config_content).unwrap_or_else(|error| {
+            panic!("Failed to parse config file {}", error);
+        }),
+        Some("toml") => toml::from_str(&config_content).unwrap_or_else(|error| {
+            panic!("Failed to parse config file {}", error);
+        }),
+        _ => serde_json::from_str(&config_content).unwrap_or_else(|error| {
+            panic!("Failed to parse config file {}", error);
+        }),
+    };
+
     Ok(config)
 }
 
@@ -161,6 +223,10 @@ fn get_all_stories<P: AsRef<Path>>(dir: P) -> Vec<Story> {
 
     let mut stories: Vec<Story> = vec![];
 
+    let re = Regex::new(r#"^\s*export const (\w+): Story"#).unwrap_or_else(|error| {
+        panic!("Failed to compile regex {}", error);
+    });
+
     for path in paths {
         let content = std::fs::read_to_string(path.clone()).unwrap_or_else(|error| {
             panic!("Failed to read file {} {}", path, error);
@@ -174,15 +240,35 @@ fn get_all_stories<P: AsRef<Path>>(dir: P) -> Vec<Story> {
             .next()
             .unwrap_or_default();
 
-        let re = Regex::new(r#"export const (\w+): Story"#).unwrap_or_else(|error| {
-            panic!("Failed to compile regex {}", error);
-        });
+        let lines: Vec<&str> = content.lines().collect();
+
+        for (index, line) in lines.iter().enumerate() {
+            let Some(captures) = re.captures(line) else {
+                continue;
+            };
+            let name = &captures[1];
+
+            let mut only = false;
+            let mut ignore = false;
+
+            for prev in lines[..index].iter().rev() {
+                let trimmed = prev.trim();
+                if trimmed.is_empty() {
+                    continue;
+                }
+                if !trimmed.starts_with("//") {
+                    break;
+                }
+                only = only || trimmed.contains("@visage-only");
+                ignore = ignore || trimmed.contains("@visage-ignore");
+            }
 
-        for (_, [hit]) in re.captures_iter(&content).map(|cap| cap.extract()) {
             let story = Story {
                 path: PathBuf::from(path.clone()),
-                name: hit.to_string(),
+                name: name.to_string(),
                 component_name: component_name.to_string(),
+                only,
+                ignore,
             };
 
             stories.push(story);
@@ -192,15 +278,74 @@ fn get_all_stories<P: AsRef<Path>>(dir: P) -> Vec<Story> {
     return stories;
 }
 
+/// Result of applying name/tag filtering: the surviving stories, how many were filtered out, and
+/// how many stories carried `// @visage-only` (0 means only-mode wasn't engaged).
+struct FilteredStories {
+    stories: Vec<Story>,
+    filtered_count: usize,
+    only_count: usize,
+}
+
+/// `name_filter` is matched as a plain substring against `Story.name` and `component_name`,
+/// matching the `visage check Button` ergonomics without risking a regex-metacharacter panic.
+fn filter_stories(stories: Vec<Story>, name_filter: Option<&str>) -> FilteredStories {
+    let total = stories.len();
+    let only_count = stories.iter().filter(|story| story.only).count();
+
+    let filtered: Vec<Story> = stories
+        .into_iter()
+        .filter(|story| !story.ignore)
+        .filter(|story| only_count == 0 || story.only)
+        .filter(|story| match name_filter {
+            Some(pattern) => story.name.contains(pattern) || story.component_name.contains(pattern),
+            None => true,
+        })
+        .collect();
+
+    FilteredStories {
+        filtered_count: total - filtered.len(),
+        stories: filtered,
+        only_count,
+    }
+}
+
+/// Per-run settings shared by every story x viewport check, bundled the way `CheckOptions`
+/// bundles the CLI flags so `check_story` doesn't grow another loose parameter per knob.
+struct CheckContext<'a> {
+    baselines: &'a BaselineStore,
+    threshold: u32,
+    compare_only: bool,
+    output_dir: Option<&'a Path>,
+}
+
 async fn check_story(
     story: &Story,
     config: &Config,
     browser: &Browser,
+    viewport: &str,
+    ctx: &CheckContext<'_>,
 ) -> Result<RegressionTestResult, Box<dyn std::error::Error>> {
+    let (width, height) = parse_viewport(viewport)?;
+
     let url = format!("{}/{}", config.base_url, story);
     let page = browser.new_page(url.clone()).await.unwrap_or_else(|error| {
         panic!("Failed to open page: {} {}", url.clone(), error);
     });
+    page.execute(
+        SetDeviceMetricsOverrideParams::builder()
+            .width(width)
+            .height(height)
+            .device_scale_factor(1.0)
+            .mobile(false)
+            .build()
+            .unwrap_or_else(|error| {
+                panic!("Failed to build viewport override {}: {}", viewport, error);
+            }),
+    )
+    .await
+    .unwrap_or_else(|error| {
+        panic!("Failed to set viewport {}: {}", viewport, error);
+    });
     page.wait_for_navigation().await.unwrap_or_else(|error| {
         panic!("Failed to navigate to page: {} {}", url.clone(), error);
     });
@@ -227,9 +372,10 @@ async fn check_story(
             );
         });
 
-    assert!(style.len() > 0, "No styles found");
+    assert!(!style.is_empty(), "No styles found");
 
     hasher.update(style);
+    let style_hash = format!("{:x}", hasher.finalize());
 
     let screenshot = page
         .screenshot(
@@ -254,25 +400,81 @@ async fn check_story(
     let visual_hash = blockhash64(&img);
 
     let current_test = RegressionTest {
-        viewport: String::from("1920x1080"),
+        viewport: viewport.to_string(),
         component: format!("{}.{}", story.name, story.path.display()),
         dom_hash: html_hash,
-        style_hash: String::new(),
+        style_hash,
         visual_hash: visual_hash.to_string(),
         timestamp: 0,
     };
 
-    let expected_test = RegressionTest {
-        viewport: current_test.viewport.clone(),
-        component: current_test.component.clone(),
-        dom_hash: current_test.dom_hash.clone(),
-        style_hash: current_test.style_hash.clone(),
-        visual_hash: current_test.visual_hash.clone(),
-        timestamp: 0,
-    };
+    let existing_baseline = ctx
+        .baselines
+        .load(&current_test.component, &current_test.viewport);
+    // Only read the baseline screenshot bytes when there's an output dir to diff into; the
+    // plain `check` path never looks at them.
+    let existing_baseline_screenshot = ctx
+        .output_dir
+        .is_some()
+        .then(|| ctx.baselines.load_screenshot(&current_test.component, viewport))
+        .flatten();
+
+    let (status, expected_test) =
+        baseline::compare(&current_test, existing_baseline, ctx.threshold, ctx.compare_only);
+
+    if !ctx.compare_only && matches!(status, RegressionTestStatus::Created) {
+        ctx.baselines.save(&current_test).unwrap_or_else(|error| {
+            panic!(
+                "Failed to write baseline for {}: {}",
+                current_test.component, error
+            );
+        });
+        ctx.baselines
+            .save_screenshot(&current_test.component, viewport, &screenshot)
+            .unwrap_or_else(|error| {
+                panic!(
+                    "Failed to write baseline screenshot for {}: {}",
+                    current_test.component, error
+                );
+            });
+    }
+
+    if let Some(output_dir) = ctx.output_dir {
+        let screenshot_path =
+            output_dir.join(artifact_file_name(&current_test.component, viewport, "png"));
+        std::fs::write(&screenshot_path, &screenshot).unwrap_or_else(|error| {
+            panic!(
+                "Failed to write screenshot {}: {}",
+                screenshot_path.display(),
+                error
+            );
+        });
+
+        if matches!(status, RegressionTestStatus::Failed) {
+            if let Some(baseline_bytes) = existing_baseline_screenshot {
+                let baseline_img = image::load_from_memory(&baseline_bytes).unwrap_or_else(|error| {
+                    panic!("Failed to load baseline screenshot {}", error);
+                });
+                let diff_path = output_dir.join(artifact_file_name(
+                    &current_test.component,
+                    viewport,
+                    "diff.png",
+                ));
+                diff::diff_images(&img, &baseline_img)
+                    .save(&diff_path)
+                    .unwrap_or_else(|error| {
+                        panic!(
+                            "Failed to write diff image {}: {}",
+                            diff_path.display(),
+                            error
+                        );
+                    });
+            }
+        }
+    }
 
     let result = RegressionTestResult {
-        status: RegressionTestStatus::Created,
+        status,
         current_test,
         expected_test,
     };
@@ -283,10 +485,66 @@ async fn check_story(
 async fn check(
     config: &Config,
     cwd: &Path,
+    options: &CheckOptions,
 ) -> Result<Vec<RegressionTestResult>, Box<dyn std::error::Error>> {
-    let stories = get_all_stories(cwd);
+    let FilteredStories {
+        mut stories,
+        filtered_count,
+        only_count,
+    } = filter_stories(get_all_stories(cwd), options.filter.as_deref());
+
+    if only_count > 0 {
+        println!(
+            "Only-mode engaged: {} stor{} tagged @visage-only, rest filtered",
+            only_count,
+            if only_count == 1 { "y" } else { "ies" }
+        );
+    }
+
+    if let Some(seed) = options.shuffle_seed {
+        let mut rng = StdRng::seed_from_u64(seed);
+        stories.shuffle(&mut rng);
+        println!("Shuffled story order with seed {}", seed);
+    }
 
-    println!("Found {} stories", stories.len());
+    let baselines = BaselineStore::new(cwd.join("visage"));
+    let output_dir = options.output_dir.as_deref();
+
+    if let Some(output_dir) = output_dir {
+        std::fs::create_dir_all(output_dir).unwrap_or_else(|error| {
+            panic!(
+                "Failed to create output directory {}: {}",
+                output_dir.display(),
+                error
+            );
+        });
+    }
+
+    let ctx = CheckContext {
+        baselines: &baselines,
+        threshold: options.threshold,
+        compare_only: options.compare_only,
+        output_dir,
+    };
+
+    let work_items: Vec<(&Story, &str)> = stories
+        .iter()
+        .flat_map(|story| {
+            config
+                .viewports
+                .iter()
+                .map(move |viewport| (story, viewport.as_str()))
+        })
+        .collect();
+
+    println!(
+        "Plan: {} checks pending ({} stories x {} viewports), {} filtered, {} jobs",
+        work_items.len(),
+        stories.len(),
+        config.viewports.len(),
+        filtered_count,
+        options.jobs
+    );
 
     let package_json_path = cwd.join("package.json");
     if !file_exists(&package_json_path) {
@@ -301,7 +559,6 @@ async fn check(
     let (browser, mut header) = Browser::launch(
         BrowserConfig::builder()
             .no_sandbox()
-            .headless_mode(HeadlessMode::True)
             .build()
             .unwrap_or_else(|error| {
                 panic!("Failed to launch browser {}", error);
@@ -320,17 +577,21 @@ async fn check(
         }
     });
 
-    let mut results: Vec<RegressionTestResult> = vec![];
-
-    for story in &stories {
-        let result = check_story(&story, config, &browser)
-            .await
-            .unwrap_or_else(|error| {
-                panic!("Failed to check story: {} {}", story, error);
-            });
-
-        results.push(result);
-    }
+    let results: Vec<RegressionTestResult> = stream::iter(&work_items)
+        .map(|(story, viewport)| {
+            let browser = &browser;
+            let ctx = &ctx;
+            async move {
+                check_story(story, config, browser, viewport, ctx)
+                    .await
+                    .unwrap_or_else(|error| {
+                        panic!("Failed to check story: {} ({}) {}", story, viewport, error);
+                    })
+            }
+        })
+        .buffer_unordered(options.jobs)
+        .collect()
+        .await;
 
     handle.await.unwrap_or_else(|error| {
         panic!("Failed to wait for browser header {}", error);
@@ -345,6 +606,91 @@ async fn check(
     Ok(results)
 }
 
+struct CheckOptions {
+    filter: Option<String>,
+    compare_only: bool,
+    threshold: u32,
+    reporter: Reporter,
+    jobs: usize,
+    shuffle_seed: Option<u64>,
+    output_dir: Option<PathBuf>,
+}
+
+/// Parses the flags and positional filter that follow the subcommand, e.g. `check Button --jobs 4`.
+fn parse_check_options(args: &[String]) -> CheckOptions {
+    let mut filter: Option<String> = None;
+    let mut compare_only = false;
+    let mut threshold = baseline::DEFAULT_HAMMING_THRESHOLD;
+    let mut reporter = Reporter::Pretty;
+    let mut jobs = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1);
+    let mut shuffle_seed: Option<u64> = None;
+    let mut output_dir: Option<PathBuf> = None;
+
+    let mut index = 2;
+    while index < args.len() {
+        match args[index].as_str() {
+            "--compare-only" => {
+                compare_only = true;
+                index += 1;
+            }
+            "--threshold" => {
+                if let Some(value) = args.get(index + 1).and_then(|value| value.parse().ok()) {
+                    threshold = value;
+                }
+                index += 2;
+            }
+            "--report" => {
+                if let Some(value) = args.get(index + 1) {
+                    reporter = Reporter::parse(value);
+                }
+                index += 2;
+            }
+            "--jobs" => {
+                if let Some(value) = args
+                    .get(index + 1)
+                    .and_then(|value| value.parse::<usize>().ok())
+                {
+                    jobs = value.max(1);
+                }
+                index += 2;
+            }
+            "--shuffle" => {
+                let seed = args.get(index + 1).and_then(|value| value.parse::<u64>().ok());
+                shuffle_seed = Some(seed.unwrap_or_else(|| {
+                    std::time::SystemTime::now()
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .map(|duration| duration.as_nanos() as u64)
+                        .unwrap_or(0)
+                }));
+                index += if seed.is_some() { 2 } else { 1 };
+            }
+            "--output-dir" => {
+                output_dir = args.get(index + 1).map(PathBuf::from);
+                index += 2;
+            }
+            arg if !arg.starts_with("--") => {
+                filter = Some(arg.to_string());
+                index += 1;
+            }
+            _ => {
+                index += 1;
+            }
+        }
+    }
+
+    CheckOptions {
+        filter,
+        compare_only,
+        threshold,
+        reporter,
+        jobs,
+        shuffle_seed,
+        output_dir,
+    }
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args: Vec<String> = std::env::args().collect();
@@ -361,27 +707,22 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         panic!("Failed to get home directory {}", error);
     });
 
-    let home_config_path = home_dir.join(".config").join("visage.json");
-    let local_config_path = cwd.join("visage.json");
-
     println!("Current working directory: {:?}", cwd);
     println!("Home directory: {:?}", home_dir);
-    println!("Local config path: {:?}", local_config_path);
-    println!("Home config path: {:?}", home_config_path);
 
-    let config_path = if local_config_path.exists() {
-        local_config_path
-    } else if home_config_path.exists() {
-        home_config_path
-    } else {
+    let config_path = find_config_path(&cwd, &home_dir).unwrap_or_else(|| {
         eprintln!("No configuration file found");
         std::process::exit(1);
-    };
+    });
+
+    println!("Config path: {:?}", config_path);
 
     let config = load_config(config_path).unwrap_or_else(|error| {
         panic!("Failed to load configuration file {}", error);
     });
 
+    let options = parse_check_options(&args);
+
     match cmd.as_str() {
         "check" => {
             let mocked_file_path = home_dir
@@ -389,17 +730,23 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 .join("tuv-galaxy")
                 .join("component-library")
                 .join("project");
-            check(&config, &mocked_file_path)
+            let results = check(&config, &mocked_file_path, &options)
                 .await
                 .unwrap_or_else(|error| {
                     panic!("Failed to check stories {}", error);
-                })
-                .into_iter()
-                .for_each(|result| {
-                    println!("Component: {}", result.current_test.component);
-                    println!("Status: {:?}", result.status);
-                    println!("Current Test: {:?}", result.current_test);
-                    println!("Expected Test: {:?}", result.expected_test);
+                });
+            options.reporter.report(&results);
+        }
+        "watch" => {
+            let mocked_file_path = home_dir
+                .join("code")
+                .join("tuv-galaxy")
+                .join("component-library")
+                .join("project");
+            watch::watch(&config, &mocked_file_path, options.threshold)
+                .await
+                .unwrap_or_else(|error| {
+                    panic!("Failed to watch stories {}", error);
                 });
         }
         _ => {
@@ -0,0 +1,101 @@
+use crate::{RegressionTestResult, RegressionTestStatus};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// Where and how a finished run's results should be surfaced.
+pub enum Reporter {
+    /// The original stdout dump, one block per result.
+    Pretty,
+    /// JUnit-style XML, one `<testsuite>` per component and one `<testcase>` per viewport checked.
+    Junit { path: PathBuf },
+}
+
+impl Reporter {
+    /// Parses a `--report` flag value such as `junit:report.xml`, falling back to `pretty`.
+    pub fn parse(arg: &str) -> Self {
+        match arg.split_once(':') {
+            Some(("junit", path)) => Reporter::Junit {
+                path: PathBuf::from(path),
+            },
+            _ => Reporter::Pretty,
+        }
+    }
+
+    pub fn report(&self, results: &[RegressionTestResult]) {
+        match self {
+            Reporter::Pretty => print_pretty(results),
+            Reporter::Junit { path } => write_junit(results, path),
+        }
+    }
+}
+
+fn print_pretty(results: &[RegressionTestResult]) {
+    for result in results {
+        println!("Component: {}", result.current_test.component);
+        println!("Status: {:?}", result.status);
+        println!("Current Test: {:?}", result.current_test);
+        println!("Expected Test: {:?}", result.expected_test);
+    }
+}
+
+fn write_junit(results: &[RegressionTestResult], path: &std::path::Path) {
+    let mut suites: BTreeMap<&str, Vec<&RegressionTestResult>> = BTreeMap::new();
+    for result in results {
+        suites
+            .entry(&result.current_test.component)
+            .or_default()
+            .push(result);
+    }
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites>\n");
+    for (component, cases) in &suites {
+        xml.push_str(&format!(
+            "  <testsuite name=\"{}\" tests=\"{}\">\n",
+            xml_escape(component),
+            cases.len()
+        ));
+
+        for result in cases {
+            xml.push_str(&format!(
+                "    <testcase name=\"{}\" classname=\"{}\">\n",
+                xml_escape(&result.current_test.viewport),
+                xml_escape(component),
+            ));
+
+            match result.status {
+                RegressionTestStatus::Failed => xml.push_str(&format!(
+                    "      <failure message=\"visual regression detected\">dom_hash={} style_hash={} visual_hash={} expected_visual_hash={}</failure>\n",
+                    xml_escape(&result.current_test.dom_hash),
+                    xml_escape(&result.current_test.style_hash),
+                    xml_escape(&result.current_test.visual_hash),
+                    xml_escape(&result.expected_test.visual_hash),
+                )),
+                RegressionTestStatus::Skipped => xml.push_str("      <skipped/>\n"),
+                RegressionTestStatus::Passed | RegressionTestStatus::Created => {}
+            }
+
+            xml.push_str("    </testcase>\n");
+        }
+
+        xml.push_str("  </testsuite>\n");
+    }
+    xml.push_str("</testsuites>\n");
+
+    if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent).unwrap_or_else(|error| {
+            panic!("Failed to create report directory {}", error);
+        });
+    }
+
+    std::fs::write(path, xml).unwrap_or_else(|error| {
+        panic!("Failed to write report to {}: {}", path.display(), error);
+    });
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,123 @@
+use crate::{
+    check_story, file_exists, get_all_stories, get_ignores, start_storybook, stop_storybook,
+    BaselineStore, CheckContext, Config, Story,
+};
+use chromiumoxide::browser::{Browser, BrowserConfig};
+use futures::StreamExt;
+use notify::{RecursiveMode, Watcher};
+use regex::Regex;
+use std::path::Path;
+
+/// Starts Storybook and the headless browser once, then re-checks only the stories whose
+/// `.stories.ts(x)` file (or a sibling file in the same directory) changes.
+pub async fn watch(
+    config: &Config,
+    root: &Path,
+    threshold: u32,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let ignores = get_ignores(root);
+    let baselines = BaselineStore::new(root.join("visage"));
+    let stories = get_all_stories(root);
+
+    println!("Watching {} stories for changes in {}", stories.len(), root.display());
+
+    let package_json_path = root.join("package.json");
+    if !file_exists(&package_json_path) {
+        eprintln!("No package.json found in the current directory");
+        std::process::exit(1);
+    }
+
+    let storybook = start_storybook().await.unwrap_or_else(|error| {
+        panic!("Failed to start storybook {}", error);
+    });
+
+    let (browser, mut header) = Browser::launch(
+        BrowserConfig::builder()
+            .no_sandbox()
+            .build()
+            .unwrap_or_else(|error| {
+                panic!("Failed to launch browser {}", error);
+            }),
+    )
+    .await
+    .unwrap_or_else(|error| {
+        panic!("Failed to launch browser {}", error);
+    });
+
+    let handle = tokio::task::spawn(async move {
+        while let Some(h) = header.next().await {
+            if h.is_err() {
+                break;
+            }
+        }
+    });
+
+    let ctx = CheckContext {
+        baselines: &baselines,
+        threshold,
+        compare_only: false,
+        output_dir: None,
+    };
+
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        let _ = tx.send(event);
+    })?;
+    watcher.watch(root, RecursiveMode::Recursive)?;
+
+    while let Some(event) = rx.recv().await {
+        let Ok(event) = event else {
+            continue;
+        };
+
+        let affected: Vec<&Story> = stories
+            .iter()
+            .filter(|story| {
+                event
+                    .paths
+                    .iter()
+                    .any(|changed| is_affected(story, changed, &ignores))
+            })
+            .collect();
+
+        if affected.is_empty() {
+            continue;
+        }
+
+        for story in affected {
+            for viewport in &config.viewports {
+                match check_story(story, config, &browser, viewport, &ctx).await {
+                    Ok(result) => println!(
+                        "{} [{}] ({}): {:?}",
+                        story.component_name, story.name, viewport, result.status
+                    ),
+                    Err(error) => eprintln!("Failed to check {} ({}): {}", story, viewport, error),
+                }
+            }
+        }
+    }
+
+    handle.await.unwrap_or_else(|error| {
+        panic!("Failed to wait for browser header {}", error);
+    });
+
+    stop_storybook(storybook).await.unwrap_or_else(|error| {
+        panic!("Failed to stop storybook {}", error);
+    });
+
+    Ok(())
+}
+
+fn is_affected(story: &Story, changed: &Path, ignores: &[String]) -> bool {
+    let changed_str = changed.to_string_lossy();
+    for ignore in ignores {
+        let re = Regex::new(&format!(r"^{}$", ignore)).unwrap_or_else(|error| {
+            panic!("Failed to compile regex {}", error);
+        });
+        if re.is_match(&changed_str) {
+            return false;
+        }
+    }
+
+    story.path == changed || story.path.parent() == changed.parent()
+}